@@ -0,0 +1,202 @@
+use tch::{Kind, Tensor};
+
+/// Output container/codec for synthesized audio returned by [`encode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioFormat {
+    Wav,
+    Ogg,
+    Aac,
+}
+
+/// Encodes a 1-D float audio `tensor` (samples in `[-1, 1]`, already at
+/// `sample_rate`) into a ready-to-serve byte buffer. Use
+/// [`crate::GPTSovits::resample`] beforehand if the tensor isn't already at
+/// the rate you want to encode at.
+pub fn encode(tensor: &Tensor, format: AudioFormat, sample_rate: usize) -> anyhow::Result<Vec<u8>> {
+    match format {
+        AudioFormat::Wav => encode_wav(tensor, sample_rate),
+        AudioFormat::Ogg => encode_ogg(tensor, sample_rate),
+        AudioFormat::Aac => encode_aac(tensor, sample_rate),
+    }
+}
+
+fn pcm16_samples(tensor: &Tensor) -> anyhow::Result<Vec<i16>> {
+    let tensor = tensor.reshape([-1]).to_kind(Kind::Float);
+    let samples = Vec::<f32>::try_from(&tensor)
+        .map_err(|e| anyhow::anyhow!("audio tensor must be 1-D float: {}", e))?;
+    Ok(samples
+        .into_iter()
+        .map(|s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+        .collect())
+}
+
+fn encode_wav(tensor: &Tensor, sample_rate: usize) -> anyhow::Result<Vec<u8>> {
+    let samples = pcm16_samples(tensor)?;
+    let data_len = (samples.len() * 2) as u32;
+    let byte_rate = sample_rate as u32 * 2;
+
+    let mut out = Vec::with_capacity(44 + data_len as usize);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(36 + data_len).to_le_bytes());
+    out.extend_from_slice(b"WAVE");
+    out.extend_from_slice(b"fmt ");
+    out.extend_from_slice(&16u32.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    out.extend_from_slice(&1u16.to_le_bytes()); // mono
+    out.extend_from_slice(&(sample_rate as u32).to_le_bytes());
+    out.extend_from_slice(&byte_rate.to_le_bytes());
+    out.extend_from_slice(&2u16.to_le_bytes()); // block align (mono, 16-bit)
+    out.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        out.extend_from_slice(&sample.to_le_bytes());
+    }
+    Ok(out)
+}
+
+/// Opus only accepts 8000/12000/16000/24000/48000 Hz, so the TTS's native
+/// 32kHz output (and any other rate a caller passes) has to be mapped to
+/// the smallest accepted rate that's at least as high, to avoid throwing
+/// quality away. Falls back to 48kHz above that.
+#[cfg(feature = "ogg")]
+fn nearest_opus_rate(sample_rate: usize) -> (audiopus::SampleRate, usize) {
+    use audiopus::SampleRate;
+    const RATES: [(usize, SampleRate); 5] = [
+        (8000, SampleRate::Hz8000),
+        (12000, SampleRate::Hz12000),
+        (16000, SampleRate::Hz16000),
+        (24000, SampleRate::Hz24000),
+        (48000, SampleRate::Hz48000),
+    ];
+    RATES
+        .iter()
+        .find(|(hz, _)| *hz >= sample_rate)
+        .copied()
+        .unwrap_or((48000, SampleRate::Hz48000))
+}
+
+/// Linear-interpolation resample of 16-bit PCM, used to bring audio onto a
+/// sample rate Opus actually accepts before encoding.
+#[cfg(feature = "ogg")]
+fn resample_linear(samples: &[i16], from_rate: usize, to_rate: usize) -> Vec<i16> {
+    if from_rate == to_rate || samples.is_empty() {
+        return samples.to_vec();
+    }
+    let ratio = to_rate as f64 / from_rate as f64;
+    let out_len = ((samples.len() as f64) * ratio).round() as usize;
+    (0..out_len)
+        .map(|i| {
+            let src_pos = i as f64 / ratio;
+            let idx = src_pos.floor() as usize;
+            let frac = src_pos - idx as f64;
+            let a = samples[idx.min(samples.len() - 1)] as f64;
+            let b = samples[(idx + 1).min(samples.len() - 1)] as f64;
+            (a + (b - a) * frac).round() as i16
+        })
+        .collect()
+}
+
+#[cfg(feature = "ogg")]
+fn encode_ogg(tensor: &Tensor, sample_rate: usize) -> anyhow::Result<Vec<u8>> {
+    use audiopus::{coder::Encoder, Application, Channels};
+    use ogg::PacketWriter;
+    use std::io::Cursor;
+
+    let (opus_rate, target_hz) = nearest_opus_rate(sample_rate);
+    let samples = resample_linear(&pcm16_samples(tensor)?, sample_rate, target_hz);
+    let mut encoder = Encoder::new(opus_rate, Channels::Mono, Application::Audio)?;
+
+    // Opus frames must be a fixed 2.5/5/10/20/40/60ms duration; use 20ms and
+    // zero-pad the final frame instead of feeding it a short slice.
+    let frame_samples = target_hz / 50;
+    let num_frames = samples.len().div_ceil(frame_samples.max(1));
+
+    let mut out = Cursor::new(Vec::new());
+    let mut writer = PacketWriter::new(&mut out);
+    let mut scratch = vec![0u8; frame_samples * 2];
+    for i in 0..num_frames {
+        let start = i * frame_samples;
+        let end = (start + frame_samples).min(samples.len());
+        let mut frame = vec![0i16; frame_samples];
+        frame[..end - start].copy_from_slice(&samples[start..end]);
+
+        let written = encoder.encode(&frame, &mut scratch)?;
+        let end_info = if i + 1 == num_frames {
+            ogg::PacketWriteEndInfo::EndStream
+        } else {
+            ogg::PacketWriteEndInfo::NormalPacket
+        };
+        writer.write_packet(scratch[..written].to_vec(), 0, end_info, start as u64)?;
+    }
+    Ok(out.into_inner())
+}
+
+#[cfg(not(feature = "ogg"))]
+fn encode_ogg(_tensor: &Tensor, _sample_rate: usize) -> anyhow::Result<Vec<u8>> {
+    anyhow::bail!("ogg output requires building gpt_sovits_rs with the `ogg` feature")
+}
+
+#[cfg(feature = "aac")]
+fn encode_aac(tensor: &Tensor, sample_rate: usize) -> anyhow::Result<Vec<u8>> {
+    use fdk_aac::enc::{Encoder, EncoderParams, Transport};
+
+    let samples = pcm16_samples(tensor)?;
+    let encoder = Encoder::new(EncoderParams {
+        bit_rate: fdk_aac::enc::BitRate::VbrVeryHigh,
+        sample_rate: sample_rate as u32,
+        transport: Transport::Adts,
+        channels: fdk_aac::enc::ChannelMode::Mono,
+    })?;
+    let mut out = Vec::new();
+    for chunk in samples.chunks(1024) {
+        out.extend_from_slice(&encoder.encode(chunk)?);
+    }
+    Ok(out)
+}
+
+#[cfg(not(feature = "aac"))]
+fn encode_aac(_tensor: &Tensor, _sample_rate: usize) -> anyhow::Result<Vec<u8>> {
+    anyhow::bail!("aac output requires building gpt_sovits_rs with the `aac` feature")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wav_header_matches_pcm_payload() {
+        let tensor = Tensor::from_slice(&[0.0f32, 0.5, -1.0, 1.0]);
+        let bytes = encode_wav(&tensor, 16000).unwrap();
+
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(&bytes[8..12], b"WAVE");
+        assert_eq!(&bytes[12..16], b"fmt ");
+        assert_eq!(u32::from_le_bytes(bytes[24..28].try_into().unwrap()), 16000);
+        assert_eq!(&bytes[36..40], b"data");
+        let data_len = u32::from_le_bytes(bytes[40..44].try_into().unwrap());
+        assert_eq!(data_len as usize, 4 * 2);
+        assert_eq!(bytes.len(), 44 + data_len as usize);
+    }
+
+    #[test]
+    fn pcm16_samples_clamp_out_of_range_floats() {
+        let tensor = Tensor::from_slice(&[2.0f32, -2.0]);
+        let samples = pcm16_samples(&tensor).unwrap();
+        assert_eq!(samples, vec![i16::MAX, -i16::MAX]);
+    }
+
+    #[cfg(feature = "ogg")]
+    #[test]
+    fn nearest_opus_rate_maps_native_32k_up_to_48k() {
+        let (_, hz) = nearest_opus_rate(32000);
+        assert_eq!(hz, 48000);
+    }
+
+    #[cfg(feature = "ogg")]
+    #[test]
+    fn nearest_opus_rate_passes_through_supported_rate() {
+        let (_, hz) = nearest_opus_rate(16000);
+        assert_eq!(hz, 16000);
+    }
+}
@@ -1,9 +1,10 @@
 use std::{collections::HashMap, str::FromStr, sync::Arc, usize};
 
 use anyhow::Ok;
-use tch::{IValue, Tensor};
+use tch::{IValue, Kind, Tensor};
 use text::{g2p_en::G2PEnConverter, g2p_jp::G2PJpConverter, g2pw::G2PWConverter, CNBertModel};
 
+pub mod audio;
 pub mod symbols;
 pub mod text;
 pub use tch::Device;
@@ -13,6 +14,7 @@ pub struct GPTSovitsConfig {
     pub g2p_en_path: String,
     pub ssl_path: String,
     pub enable_jp: bool,
+    pub is_half: bool,
 }
 
 impl GPTSovitsConfig {
@@ -22,6 +24,7 @@ impl GPTSovitsConfig {
             g2p_en_path,
             ssl_path,
             enable_jp: false,
+            is_half: false,
         }
     }
 
@@ -34,7 +37,20 @@ impl GPTSovitsConfig {
         Self { enable_jp, ..self }
     }
 
+    /// Loads the BERT/SSL/synthesizer weights in FP16 on a CUDA `Device`,
+    /// roughly halving model memory and improving throughput. No-op on CPU,
+    /// where FP16 kernels aren't available.
+    pub fn with_half(self, is_half: bool) -> Self {
+        Self { is_half, ..self }
+    }
+
     pub fn build(&self, device: Device) -> anyhow::Result<GPTSovits> {
+        let kind = if self.is_half && device.is_cuda() {
+            Kind::Half
+        } else {
+            Kind::Float
+        };
+
         let (cn_bert, g2pw) = match &self.cn_setting {
             Some((g2pw_path, cn_bert_path)) => {
                 let tokenizer = tokenizers::Tokenizer::from_str(text::g2pw::G2PW_TOKENIZER)
@@ -43,9 +59,10 @@ impl GPTSovitsConfig {
 
                 let mut bert = tch::CModule::load_on_device(&cn_bert_path, device)?;
                 bert.set_eval();
+                bert.to(device, kind, false);
 
                 let cn_bert_model = CNBertModel::new(Arc::new(bert), tokenizer.clone());
-                let g2pw = G2PWConverter::new_with_device(g2pw_path, tokenizer.clone(), device)?;
+                let g2pw = G2PWConverter::new_with_device(g2pw_path, tokenizer.clone(), device, kind)?;
 
                 (cn_bert_model, g2pw)
             }
@@ -54,6 +71,7 @@ impl GPTSovitsConfig {
 
         let mut ssl = tch::CModule::load_on_device(&self.ssl_path, device).unwrap();
         ssl.set_eval();
+        ssl.to(device, kind, false);
 
         Ok(GPTSovits {
             zh_bert: cn_bert,
@@ -66,6 +84,7 @@ impl GPTSovitsConfig {
             jieba: jieba_rs::Jieba::new(),
             speakers: HashMap::new(),
             enable_jp: self.enable_jp,
+            kind,
         })
     }
 }
@@ -79,6 +98,10 @@ pub struct Speaker {
     ref_audio_32k: Tensor,
     ref_phone_seq: Tensor,
     ref_bert_seq: Tensor,
+    /// Dtype `gpt_sovits`'s weights were cast to; `bert_seq` arriving from
+    /// [`GPTSovits::infer`] is cast to match before the forward pass, and
+    /// the resulting audio is cast back to `Float`.
+    kind: Kind,
 }
 
 impl Speaker {
@@ -95,6 +118,12 @@ impl Speaker {
     }
 
     pub fn infer(&self, text_phone_seq: &Tensor, bert_seq: &Tensor) -> anyhow::Result<Tensor> {
+        let bert_seq = if self.kind == Kind::Half {
+            bert_seq.to_kind(Kind::Half)
+        } else {
+            bert_seq.shallow_clone()
+        };
+
         let audio = self.gpt_sovits.forward_ts(&[
             &self.ssl_content,
             &self.ref_audio_32k,
@@ -104,7 +133,7 @@ impl Speaker {
             &bert_seq,
         ])?;
 
-        Ok(audio)
+        Ok(audio.to_kind(Kind::Float))
     }
 }
 
@@ -122,9 +151,14 @@ pub struct GPTSovits {
     jieba: jieba_rs::Jieba,
 
     enable_jp: bool,
+
+    /// `Kind::Half` when FP16 inference is enabled (CUDA only), otherwise
+    /// `Kind::Float`. See [`GPTSovitsConfig::with_half`].
+    kind: Kind,
 }
 
 impl GPTSovits {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         zh_bert: CNBertModel,
         g2pw: G2PWConverter,
@@ -147,6 +181,17 @@ impl GPTSovits {
             ssl,
             jieba,
             enable_jp,
+            kind: Kind::Float,
+        }
+    }
+
+    /// Casts `t` to this instance's inference dtype (FP16 on CUDA when
+    /// [`GPTSovitsConfig::with_half`] was set, FP32 otherwise).
+    fn cast(&self, t: &Tensor) -> Tensor {
+        if self.kind == Kind::Half {
+            t.to_kind(Kind::Half)
+        } else {
+            t.shallow_clone()
         }
     }
 
@@ -161,6 +206,7 @@ impl GPTSovits {
         tch::no_grad(|| {
             let mut gpt_sovits = tch::CModule::load_on_device(gpt_sovits_path, self.device)?;
             gpt_sovits.set_eval();
+            gpt_sovits.to(self.device, self.kind, false);
 
             // 避免句首吞字
             let ref_text = if !ref_text.ends_with(['。', '.']) {
@@ -173,12 +219,15 @@ impl GPTSovits {
                 .to_device(self.device)
                 .unsqueeze(0);
 
+            // resample() casts its input to self.kind, the dtype the
+            // ssl/gpt_sovits modules were cast to.
             let ref_audio_16k = self.resample(&ref_audio, ref_audio_sr, 16000)?;
             let ref_audio_32k = self.resample(&ref_audio, ref_audio_sr, 32000)?;
 
-            let ssl_content = self.ssl.forward_ts(&[&ref_audio_16k])?;
+            let ssl_content = self.cast(&self.ssl.forward_ts(&[&ref_audio_16k])?);
 
             let (ref_phone_seq, ref_bert_seq) = text::get_phone_and_bert(self, &ref_text)?;
+            let ref_bert_seq = self.cast(&ref_bert_seq);
 
             let speaker = Speaker {
                 name: name.to_string(),
@@ -188,6 +237,7 @@ impl GPTSovits {
                 ref_audio_32k,
                 ref_phone_seq,
                 ref_bert_seq,
+                kind: self.kind,
             };
 
             self.speakers.insert(name.to_string(), speaker);
@@ -197,10 +247,12 @@ impl GPTSovits {
 
     pub fn resample(&self, audio: &Tensor, sr: usize, target_sr: usize) -> anyhow::Result<Tensor> {
         tch::no_grad(|| {
+            // `self.ssl` is the module the resample method lives on, so its
+            // input must match whatever dtype that module was cast to.
             let resample = self.ssl.method_is(
                 "resample",
                 &[
-                    &IValue::Tensor(audio.shallow_clone()),
+                    &IValue::Tensor(self.cast(audio)),
                     &IValue::Int(sr as i64),
                     &IValue::Int(target_sr as i64),
                 ],
@@ -212,6 +264,23 @@ impl GPTSovits {
         })
     }
 
+    /// Resamples a 32kHz audio tensor (the rate every `infer*` method
+    /// returns) to `sample_rate` and encodes it as `format`, ready to be
+    /// served directly to a client.
+    pub fn encode_audio(
+        &self,
+        audio_32k: &Tensor,
+        format: audio::AudioFormat,
+        sample_rate: usize,
+    ) -> anyhow::Result<Vec<u8>> {
+        let audio = if sample_rate == 32000 {
+            audio_32k.shallow_clone()
+        } else {
+            self.resample(audio_32k, 32000, sample_rate)?
+        };
+        audio::encode(&audio, format, sample_rate)
+    }
+
     /// generate a audio tensor from text
     pub fn infer(&self, speaker: &str, target_text: &str) -> anyhow::Result<Tensor> {
         log::debug!("start infer");
@@ -227,30 +296,64 @@ impl GPTSovits {
         })
     }
 
-    pub fn segment_infer(
+    /// Splits `target_text` into chunks per `split_options` and synthesizes
+    /// them one at a time, invoking `callback` with each chunk's audio
+    /// tensor as soon as it's ready instead of waiting for the whole text
+    /// to finish. `callback` returning `false` stops synthesis early (e.g.
+    /// the consumer hung up).
+    pub fn infer_stream(
         &self,
         speaker: &str,
         target_text: &str,
-        split_chunk_size: usize,
-    ) -> anyhow::Result<Tensor> {
+        split_options: text::SplitOptions,
+        mut callback: impl FnMut(Tensor) -> bool,
+    ) -> anyhow::Result<()> {
         tch::no_grad(|| {
-            let mut audios = vec![];
-            let split_chunk_size = if split_chunk_size == 0 {
-                50
-            } else {
-                split_chunk_size
-            };
-            let chunks = crate::text::split_text(target_text, split_chunk_size);
-            log::debug!("segment_infer split_text result: {:#?}", chunks);
+            let chunks = crate::text::split_text_with_options(target_text, &split_options);
+            log::debug!("infer_stream split_text result: {:#?}", chunks);
             for target_text in chunks {
                 let audio = self.infer(speaker, target_text)?;
-                audios.push(audio);
-            }
-            if !audios.is_empty() {
-                Ok(Tensor::cat(&audios, 0))
-            } else {
-                Err(anyhow::anyhow!("no audio generated"))
+                if !callback(audio) {
+                    break;
+                }
             }
+            Ok(())
         })
     }
+
+    /// Convenience wrapper over [`GPTSovits::infer_stream`] that drains the
+    /// whole stream and concatenates it into a single tensor; latency scales
+    /// with the total text length, so prefer `infer_stream` directly when
+    /// the caller can consume audio incrementally. Thin wrapper over
+    /// [`GPTSovits::segment_infer_with_options`] kept for callers that don't
+    /// need to customize the break characters.
+    pub fn segment_infer(
+        &self,
+        speaker: &str,
+        target_text: &str,
+        split_chunk_size: usize,
+    ) -> anyhow::Result<Tensor> {
+        self.segment_infer_with_options(speaker, target_text, text::SplitOptions::new(split_chunk_size))
+    }
+
+    /// Same as [`GPTSovits::segment_infer`], but takes a full
+    /// [`text::SplitOptions`] so callers can supply their own `break_chars`
+    /// set instead of only the default punctuation.
+    pub fn segment_infer_with_options(
+        &self,
+        speaker: &str,
+        target_text: &str,
+        split_options: text::SplitOptions,
+    ) -> anyhow::Result<Tensor> {
+        let mut audios = vec![];
+        self.infer_stream(speaker, target_text, split_options, |audio| {
+            audios.push(audio);
+            true
+        })?;
+        if !audios.is_empty() {
+            Ok(Tensor::cat(&audios, 0))
+        } else {
+            Err(anyhow::anyhow!("no audio generated"))
+        }
+    }
 }
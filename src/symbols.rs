@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+
+/// Phoneme/punctuation inventory shared by every language's g2p path.
+///
+/// The synthesizer's embedding table is indexed by position in this list, so
+/// entries must never be reordered or removed once a checkpoint depends on
+/// them; new symbols are only ever appended.
+const SYMBOL_LIST: &[&str] = &[
+    "_", "pad", "UNK", "SP", "!", "?", ".", ",", "、", "，", "。", "？", "！",
+    // zh initials
+    "b", "c", "ch", "d", "f", "g", "h", "j", "k", "l", "m", "n", "p", "q", "r", "s", "sh", "t",
+    "x", "z", "zh",
+    // zh finals (with tone suffix 1-5)
+    "a1", "a2", "a3", "a4", "a5", "ai1", "ai2", "ai3", "ai4", "ai5", "an1", "an2", "an3", "an4",
+    "an5", "ang1", "ang2", "ang3", "ang4", "ang5", "ao1", "ao2", "ao3", "ao4", "ao5", "e1", "e2",
+    "e3", "e4", "e5", "ei1", "ei2", "ei3", "ei4", "ei5", "en1", "en2", "en3", "en4", "en5", "eng1",
+    "eng2", "eng3", "eng4", "eng5", "er1", "er2", "er3", "er4", "er5", "i1", "i2", "i3", "i4",
+    "i5", "ia1", "ia2", "ia3", "ia4", "ia5", "ian1", "ian2", "ian3", "ian4", "ian5", "iang1",
+    "iang2", "iang3", "iang4", "iang5", "iao1", "iao2", "iao3", "iao4", "iao5", "ie1", "ie2",
+    "ie3", "ie4", "ie5", "in1", "in2", "in3", "in4", "in5", "ing1", "ing2", "ing3", "ing4",
+    "ing5", "iong1", "iong2", "iong3", "iong4", "iong5", "iu1", "iu2", "iu3", "iu4", "iu5", "o1",
+    "o2", "o3", "o4", "o5", "ong1", "ong2", "ong3", "ong4", "ong5", "ou1", "ou2", "ou3", "ou4",
+    "ou5", "u1", "u2", "u3", "u4", "u5", "ua1", "ua2", "ua3", "ua4", "ua5", "uai1", "uai2",
+    "uai3", "uai4", "uai5", "uan1", "uan2", "uan3", "uan4", "uan5", "uang1", "uang2", "uang3",
+    "uang4", "uang5", "ue1", "ue2", "ue3", "ue4", "ue5", "ui1", "ui2", "ui3", "ui4", "ui5", "un1",
+    "un2", "un3", "un4", "un5", "uo1", "uo2", "uo3", "uo4", "uo5", "v1", "v2", "v3", "v4", "v5",
+    "van1", "van2", "van3", "van4", "van5", "ve1", "ve2", "ve3", "ve4", "ve5", "vn1", "vn2",
+    "vn3", "vn4", "vn5",
+    // english arpabet (CMU-style, stress folded into the vowel)
+    "AA", "AE", "AH", "AO", "AW", "AY", "B", "CH", "D", "DH", "EH", "ER", "EY", "F", "G", "HH",
+    "IH", "IY", "JH", "K", "L", "M", "N", "NG", "OW", "OY", "P", "R", "S", "SH", "T", "TH", "UH",
+    "UW", "V", "W", "Y", "Z", "ZH",
+    // japanese mora
+    "a", "i", "u", "e", "o", "N", "I", "U", "cl", "pau", "ka", "ki", "ku", "ke", "ko", "sa", "shi",
+    "su", "se", "so", "ta", "chi", "tsu", "te", "to", "na", "ni", "nu", "ne", "no", "ha", "hi",
+    "fu", "he", "ho", "ma", "mi", "mu", "me", "mo", "ya", "yu", "yo", "ra", "ri", "ru", "re", "ro",
+    "wa", "wo", "ga", "gi", "gu", "ge", "go", "za", "ji", "zu", "ze", "zo", "da", "de", "do", "ba",
+    "bi", "bu", "be", "bo", "pa", "pi", "pu", "pe", "po",
+];
+
+pub static SYMBOLS: Lazy<HashMap<String, i64>> = Lazy::new(|| {
+    SYMBOL_LIST
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (s.to_string(), i as i64))
+        .collect()
+});
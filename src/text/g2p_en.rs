@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+use std::fs;
+
+use tch::Tensor;
+
+use crate::GPTSovits;
+
+use super::{symbol_id, zero_bert};
+
+/// English grapheme-to-phoneme converter backed by a CMUdict-style lookup
+/// table (`word -> space separated arpabet phones`), one entry per line.
+#[derive(Debug)]
+pub struct G2PEnConverter {
+    dict: HashMap<String, Vec<String>>,
+}
+
+impl G2PEnConverter {
+    pub fn new(dict_path: &str) -> Self {
+        let dict = fs::read_to_string(dict_path)
+            .map(|content| {
+                content
+                    .lines()
+                    .filter_map(|line| {
+                        let mut parts = line.split_whitespace();
+                        let word = parts.next()?.to_lowercase();
+                        let phones: Vec<String> = parts.map(|p| p.to_string()).collect();
+                        if phones.is_empty() {
+                            None
+                        } else {
+                            Some((word, phones))
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { dict }
+    }
+
+    /// Looks up a word's phones, falling back to one symbol per ASCII letter
+    /// (the letter upper-cased, not its spoken name) when it isn't in the
+    /// dictionary (out-of-vocabulary words are rare enough in practice that
+    /// this is an acceptable degradation).
+    fn phones_for_word(&self, word: &str) -> Vec<String> {
+        let lower = word.to_lowercase();
+        if let Some(phones) = self.dict.get(&lower) {
+            return phones.clone();
+        }
+        lower
+            .chars()
+            .filter(|c| c.is_ascii_alphabetic())
+            .map(|c| c.to_ascii_uppercase().to_string())
+            .collect()
+    }
+}
+
+pub(crate) fn get_phone_and_bert(gsv: &GPTSovits, text: &str) -> anyhow::Result<(Tensor, Tensor)> {
+    let phone_ids: Vec<i64> = text
+        .split_whitespace()
+        .flat_map(|word| gsv.g2p_en.phones_for_word(word))
+        .map(|symbol| symbol_id(gsv, &symbol))
+        .collect();
+
+    let phone_seq = Tensor::from_slice(&phone_ids).to_device(gsv.device);
+    let bert_seq = zero_bert(gsv, phone_ids.len() as i64);
+    Ok((phone_seq, bert_seq))
+}
@@ -0,0 +1,107 @@
+use tch::Tensor;
+
+use crate::GPTSovits;
+
+use super::{symbol_id, zero_bert};
+
+/// Japanese grapheme-to-phoneme converter. Every hiragana/katakana mora is
+/// mapped to its phone symbol directly (youon/small-kana digraphs fall back
+/// to their base vowel/consonant, since this isn't a full pronunciation
+/// dictionary); kanji and punctuation are treated as a pause, since full
+/// kanji reading requires a dictionary this crate doesn't ship.
+#[derive(Debug, Default)]
+pub struct G2PJpConverter;
+
+impl G2PJpConverter {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn phone_for_char(c: char) -> &'static str {
+        match c {
+            'あ' | 'ア' | 'ぁ' | 'ァ' => "a",
+            'い' | 'イ' | 'ぃ' | 'ィ' => "i",
+            'う' | 'ウ' | 'ぅ' | 'ゥ' => "u",
+            'え' | 'エ' | 'ぇ' | 'ェ' => "e",
+            'お' | 'オ' | 'ぉ' | 'ォ' => "o",
+            'か' | 'カ' => "ka",
+            'き' | 'キ' => "ki",
+            'く' | 'ク' => "ku",
+            'け' | 'ケ' => "ke",
+            'こ' | 'コ' => "ko",
+            'が' | 'ガ' => "ga",
+            'ぎ' | 'ギ' => "gi",
+            'ぐ' | 'グ' => "gu",
+            'げ' | 'ゲ' => "ge",
+            'ご' | 'ゴ' => "go",
+            'さ' | 'サ' => "sa",
+            'し' | 'シ' => "shi",
+            'す' | 'ス' => "su",
+            'せ' | 'セ' => "se",
+            'そ' | 'ソ' => "so",
+            'ざ' | 'ザ' => "za",
+            'じ' | 'ジ' | 'ぢ' | 'ヂ' => "ji",
+            'ず' | 'ズ' | 'づ' | 'ヅ' => "zu",
+            'ぜ' | 'ゼ' => "ze",
+            'ぞ' | 'ゾ' => "zo",
+            'た' | 'タ' => "ta",
+            'ち' | 'チ' => "chi",
+            'つ' | 'ツ' => "tsu",
+            'て' | 'テ' => "te",
+            'と' | 'ト' => "to",
+            'だ' | 'ダ' => "da",
+            'で' | 'デ' => "de",
+            'ど' | 'ド' => "do",
+            'な' | 'ナ' => "na",
+            'に' | 'ニ' => "ni",
+            'ぬ' | 'ヌ' => "nu",
+            'ね' | 'ネ' => "ne",
+            'の' | 'ノ' => "no",
+            'は' | 'ハ' => "ha",
+            'ひ' | 'ヒ' => "hi",
+            'ふ' | 'フ' => "fu",
+            'へ' | 'ヘ' => "he",
+            'ほ' | 'ホ' => "ho",
+            'ば' | 'バ' => "ba",
+            'び' | 'ビ' => "bi",
+            'ぶ' | 'ブ' => "bu",
+            'べ' | 'ベ' => "be",
+            'ぼ' | 'ボ' => "bo",
+            'ぱ' | 'パ' => "pa",
+            'ぴ' | 'ピ' => "pi",
+            'ぷ' | 'プ' => "pu",
+            'ぺ' | 'ペ' => "pe",
+            'ぽ' | 'ポ' => "po",
+            'ま' | 'マ' => "ma",
+            'み' | 'ミ' => "mi",
+            'む' | 'ム' => "mu",
+            'め' | 'メ' => "me",
+            'も' | 'モ' => "mo",
+            'や' | 'ヤ' | 'ゃ' | 'ャ' => "ya",
+            'ゆ' | 'ユ' | 'ゅ' | 'ュ' => "yu",
+            'よ' | 'ヨ' | 'ょ' | 'ョ' => "yo",
+            'ら' | 'ラ' => "ra",
+            'り' | 'リ' => "ri",
+            'る' | 'ル' => "ru",
+            'れ' | 'レ' => "re",
+            'ろ' | 'ロ' => "ro",
+            'わ' | 'ワ' => "wa",
+            'を' | 'ヲ' => "wo",
+            'ん' | 'ン' => "N",
+            'っ' | 'ッ' => "cl",
+            _ => "pau",
+        }
+    }
+}
+
+pub(crate) fn get_phone_and_bert(gsv: &GPTSovits, text: &str) -> anyhow::Result<(Tensor, Tensor)> {
+    let phone_ids: Vec<i64> = text
+        .chars()
+        .map(G2PJpConverter::phone_for_char)
+        .map(|symbol| symbol_id(gsv, symbol))
+        .collect();
+
+    let phone_seq = Tensor::from_slice(&phone_ids).to_device(gsv.device);
+    let bert_seq = zero_bert(gsv, phone_ids.len() as i64);
+    Ok((phone_seq, bert_seq))
+}
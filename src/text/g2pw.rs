@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use tch::{IValue, Kind, Tensor};
+use tokenizers::Tokenizer;
+
+/// Tokenizer config for the G2PW polyphone-disambiguation model, embedded so
+/// `GPTSovitsConfig::build` doesn't need a second file path for it.
+pub const G2PW_TOKENIZER: &str = include_str!("../../resources/g2pw_tokenizer.json");
+
+/// Wraps the G2PW torchscript model that picks the correct pinyin reading
+/// for polyphonic Chinese characters given their surrounding word.
+#[derive(Debug)]
+pub struct G2PWConverter {
+    model: Option<Arc<tch::CModule>>,
+    tokenizer: Option<Arc<Tokenizer>>,
+    device: tch::Device,
+}
+
+impl G2PWConverter {
+    pub fn new_with_device(
+        model_path: &str,
+        tokenizer: Arc<Tokenizer>,
+        device: tch::Device,
+        kind: Kind,
+    ) -> anyhow::Result<Self> {
+        let mut model = tch::CModule::load_on_device(model_path, device)?;
+        model.to(device, kind, false);
+        Ok(Self {
+            model: Some(Arc::new(model)),
+            tokenizer: Some(tokenizer),
+            device,
+        })
+    }
+
+    /// Placeholder converter used when the caller doesn't provide a G2PW
+    /// model; any attempt to phonemize Chinese text with it fails loudly
+    /// instead of silently producing garbage pinyin.
+    pub fn empty() -> Self {
+        Self {
+            model: None,
+            tokenizer: None,
+            device: tch::Device::Cpu,
+        }
+    }
+
+    /// Returns the pinyin (with tone digit) for `word[char_index]`.
+    pub fn convert(&self, word: &str, char_index: usize) -> anyhow::Result<String> {
+        let model = self
+            .model
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("g2pw model not loaded, call GPTSovitsConfig::with_chinese first"))?;
+        let tokenizer = self.tokenizer.as_ref().unwrap();
+
+        let encoding = tokenizer
+            .encode(word, true)
+            .map_err(|e| anyhow::anyhow!("tokenize error: {}", e))?;
+        let ids: Vec<i64> = encoding.get_ids().iter().map(|&id| id as i64).collect();
+        let input = Tensor::from_slice(&ids)
+            .unsqueeze(0)
+            .to_device(self.device);
+        let position = Tensor::from_slice(&[char_index as i64]).to_device(self.device);
+
+        let output = model.method_is(
+            "forward",
+            &[&IValue::Tensor(input), &IValue::Tensor(position)],
+        )?;
+        let pinyin = match output {
+            IValue::String(s) => s,
+            _ => anyhow::bail!("unexpected g2pw model output"),
+        };
+        Ok(pinyin)
+    }
+}
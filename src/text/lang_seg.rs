@@ -0,0 +1,122 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::{classify_char, Lang};
+
+/// Minimum grapheme count for a run to stand on its own; shorter runs get
+/// folded into a neighbouring run of the same language so that e.g. a
+/// single Latin letter inside a Chinese word ("A型") doesn't get routed
+/// through the english g2p by itself.
+const MIN_RUN_LEN: usize = 2;
+
+/// Splits `text` into contiguous same-language runs, attaching digits and
+/// punctuation to whichever run they're adjacent to, then folding minority
+/// runs that are too short to be meaningful on their own.
+pub(super) fn segment_runs(text: &str) -> Vec<(Lang, String)> {
+    let graphemes: Vec<&str> = text.graphemes(true).collect();
+    if graphemes.is_empty() {
+        return vec![];
+    }
+
+    let mut labels: Vec<Option<Lang>> = graphemes
+        .iter()
+        .map(|g| g.chars().next().and_then(classify_char))
+        .collect();
+
+    // Attach unclassified graphemes (digits, punctuation, whitespace) to the
+    // preceding run, or to the following one if they lead the string.
+    for i in 0..labels.len() {
+        if labels[i].is_none() {
+            labels[i] = if i == 0 {
+                labels[i + 1..].iter().flatten().next().copied()
+            } else {
+                labels[i - 1]
+            };
+        }
+    }
+    let fallback = labels.iter().flatten().next().copied().unwrap_or(Lang::Zh);
+    for label in labels.iter_mut() {
+        label.get_or_insert(fallback);
+    }
+
+    let mut runs: Vec<(Lang, String)> = vec![];
+    for (grapheme, lang) in graphemes.iter().zip(labels.iter()) {
+        let lang = lang.unwrap();
+        match runs.last_mut() {
+            Some((last_lang, s)) if *last_lang == lang => s.push_str(grapheme),
+            _ => runs.push((lang, grapheme.to_string())),
+        }
+    }
+
+    fold_minor_runs(runs)
+}
+
+/// Merges a short run into its neighbours when both sides share a language
+/// the short run itself isn't, keeping isolated foreign-looking characters
+/// folded into the dominant surrounding language instead of becoming their
+/// own one-off run. A short run at the very start or end of the text only
+/// has one neighbour, so it folds into that one directly.
+fn fold_minor_runs(mut runs: Vec<(Lang, String)>) -> Vec<(Lang, String)> {
+    // Sandwiched runs first: both neighbours agree on a language the run
+    // itself isn't, so there's no ambiguity about which side it belongs to.
+    let mut i = 1;
+    while i + 1 < runs.len() {
+        let is_short = runs[i].1.graphemes(true).count() < MIN_RUN_LEN;
+        let sandwiched = runs[i - 1].0 == runs[i + 1].0 && runs[i].0 != runs[i - 1].0;
+        if is_short && sandwiched {
+            let mid = runs.remove(i).1;
+            let next = runs.remove(i).1;
+            runs[i - 1].1.push_str(&mid);
+            runs[i - 1].1.push_str(&next);
+        } else {
+            i += 1;
+        }
+    }
+
+    // A short run leading/trailing the text has only one neighbour to fold
+    // into; handled after the sandwich pass so a middle run isn't folded
+    // the wrong way before its other neighbour gets a chance to match it.
+    if runs.len() > 1 && runs[0].1.graphemes(true).count() < MIN_RUN_LEN {
+        let leading = runs.remove(0).1;
+        runs[0].1 = leading + &runs[0].1;
+    }
+    if runs.len() > 1 && runs[runs.len() - 1].1.graphemes(true).count() < MIN_RUN_LEN {
+        let trailing = runs.pop().unwrap().1;
+        let last = runs.len() - 1;
+        runs[last].1.push_str(&trailing);
+    }
+
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folds_isolated_latin_letter_inside_chinese() {
+        assert_eq!(segment_runs("A型"), vec![(Lang::Zh, "A型".to_string())]);
+        assert_eq!(segment_runs("A型血"), vec![(Lang::Zh, "A型血".to_string())]);
+        assert_eq!(
+            segment_runs("这是A型"),
+            vec![(Lang::Zh, "这是A型".to_string())]
+        );
+    }
+
+    #[test]
+    fn keeps_long_enough_runs_separate() {
+        assert_eq!(
+            segment_runs("这是 a mixed 文章です"),
+            vec![
+                (Lang::Zh, "这是 ".to_string()),
+                (Lang::En, "a mixed ".to_string()),
+                (Lang::Zh, "文章".to_string()),
+                (Lang::Jp, "です".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_input_yields_no_runs() {
+        assert_eq!(segment_runs(""), vec![]);
+    }
+}
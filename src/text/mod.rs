@@ -0,0 +1,266 @@
+//! Text-to-phoneme pipeline: language segmentation on top of one g2p +
+//! bert-feature path per supported language. `g2p_en`, `g2p_jp`, `g2pw` and
+//! `zh_bert` are part of this same pass, not pre-existing converters it
+//! merely routes between — there was no per-language g2p module before this,
+//! so segmentation landed together with the converters it dispatches to.
+
+pub mod g2p_en;
+pub mod g2p_jp;
+pub mod g2pw;
+mod lang_seg;
+pub mod zh_bert;
+
+pub use zh_bert::CNBertModel;
+
+use tch::Tensor;
+
+use crate::GPTSovits;
+
+/// Hidden size of the BERT feature stream, shared across every language path
+/// so phone and bert tensors from different converters can be concatenated.
+pub(crate) const BERT_FEATURE_DIM: i64 = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Lang {
+    Zh,
+    En,
+    Jp,
+}
+
+/// Languages that don't carry their own BERT model (en, jp) still need a
+/// bert-feature tensor of the right shape and dtype so `Tensor::cat` lines
+/// up; this is a zero-filled stand-in for those positions, matching the
+/// reference Python implementation.
+pub(crate) fn zero_bert(gsv: &GPTSovits, len: i64) -> Tensor {
+    Tensor::zeros([BERT_FEATURE_DIM, len], (gsv.kind, gsv.device))
+}
+
+pub(crate) fn symbol_id(gsv: &GPTSovits, symbol: &str) -> i64 {
+    gsv.symbols
+        .get(symbol)
+        .copied()
+        .unwrap_or_else(|| gsv.symbols["UNK"])
+}
+
+fn classify_char(c: char) -> Option<Lang> {
+    match c {
+        '\u{4e00}'..='\u{9fff}' => Some(Lang::Zh),
+        '\u{3040}'..='\u{30ff}' => Some(Lang::Jp),
+        c if c.is_ascii_alphabetic() => Some(Lang::En),
+        _ => None,
+    }
+}
+
+fn phone_and_bert_for_lang(
+    gsv: &GPTSovits,
+    text: &str,
+    lang: Lang,
+) -> anyhow::Result<(Tensor, Tensor)> {
+    match lang {
+        Lang::Zh => zh_bert::get_phone_and_bert(gsv, text),
+        Lang::En => g2p_en::get_phone_and_bert(gsv, text),
+        Lang::Jp => g2p_jp::get_phone_and_bert(gsv, text),
+    }
+}
+
+/// Phonemizes `text` and produces its matching bert-feature tensor.
+///
+/// `text` may freely mix Chinese, Japanese and English within a single
+/// sentence: it is first split into contiguous same-language runs by
+/// [`lang_seg::segment_runs`], each run is routed to its matching g2p + bert
+/// path, and the resulting phone-id / bert-feature tensors are concatenated
+/// back together in order.
+pub fn get_phone_and_bert(gsv: &GPTSovits, text: &str) -> anyhow::Result<(Tensor, Tensor)> {
+    let runs = lang_seg::segment_runs(text);
+    if runs.is_empty() {
+        return Ok((
+            Tensor::from_slice(&[] as &[i64]).to_device(gsv.device),
+            zero_bert(gsv, 0),
+        ));
+    }
+
+    let mut phone_parts = Vec::with_capacity(runs.len());
+    let mut bert_parts = Vec::with_capacity(runs.len());
+    for (lang, run_text) in runs {
+        let (phones, bert) = phone_and_bert_for_lang(gsv, &run_text, lang)?;
+        phone_parts.push(phones);
+        bert_parts.push(bert);
+    }
+
+    Ok((Tensor::cat(&phone_parts, 0), Tensor::cat(&bert_parts, 1)))
+}
+
+/// Delimiters `split_text_with_options` greedily breaks clauses on by
+/// default, mirroring the reference implementation's `cut_punc` control.
+const DEFAULT_BREAK_CHARS: &str = ",.;!?,。;!?、\n";
+
+/// Options controlling how [`split_text_with_options`] chunks text, mirroring
+/// the `cut_punc` control of the reference Python API.
+#[derive(Debug, Clone)]
+pub struct SplitOptions {
+    /// Maximum number of chars per chunk.
+    pub split_chunk_size: usize,
+    /// Chars that end a clause; a chunk is never broken except at one of
+    /// these (or mid-clause, if a single clause alone exceeds the limit).
+    pub break_chars: Vec<char>,
+}
+
+impl SplitOptions {
+    pub fn new(split_chunk_size: usize) -> Self {
+        Self {
+            split_chunk_size: if split_chunk_size == 0 {
+                50
+            } else {
+                split_chunk_size
+            },
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for SplitOptions {
+    fn default() -> Self {
+        Self {
+            split_chunk_size: 50,
+            break_chars: DEFAULT_BREAK_CHARS.chars().collect(),
+        }
+    }
+}
+
+/// Splits `text` into chunks of at most `split_chunk_size` chars, breaking
+/// only on the default punctuation set. Thin wrapper over
+/// [`split_text_with_options`] kept for callers that don't need to
+/// customize the break characters.
+pub fn split_text(text: &str, split_chunk_size: usize) -> Vec<&str> {
+    split_text_with_options(text, &SplitOptions::new(split_chunk_size))
+}
+
+/// Splits `text` into chunks of at most `options.split_chunk_size` chars,
+/// accumulating whole clauses (delimited by `options.break_chars`) into a
+/// chunk until the next clause would overflow it. A single clause longer
+/// than the limit is hard-sliced by char count as a last resort, since
+/// there's nowhere else to break it.
+pub fn split_text_with_options<'a>(text: &'a str, options: &SplitOptions) -> Vec<&'a str> {
+    if text.is_empty() || options.split_chunk_size == 0 {
+        return vec![];
+    }
+
+    let mut chunks = vec![];
+    let mut current: Option<(usize, usize, usize)> = None; // (start, end, char_count)
+
+    for clause in split_into_clauses(text, &options.break_chars) {
+        let start = offset_in(text, clause);
+        let end = start + clause.len();
+        let char_count = clause.chars().count();
+
+        if char_count > options.split_chunk_size {
+            if let Some((cs, ce, _)) = current.take() {
+                chunks.push(&text[cs..ce]);
+            }
+            chunks.extend(hard_split(clause, options.split_chunk_size));
+            continue;
+        }
+
+        current = match current {
+            Some((cs, ce, cc)) if cc + char_count <= options.split_chunk_size => {
+                Some((cs, end, cc + char_count))
+            }
+            Some((cs, ce, _)) => {
+                chunks.push(&text[cs..ce]);
+                Some((start, end, char_count))
+            }
+            None => Some((start, end, char_count)),
+        };
+    }
+    if let Some((cs, ce, _)) = current {
+        chunks.push(&text[cs..ce]);
+    }
+    chunks
+}
+
+/// Splits `text` at each `break_chars` occurrence, keeping the delimiter
+/// attached to the end of the clause it closes.
+fn split_into_clauses<'a>(text: &'a str, break_chars: &[char]) -> Vec<&'a str> {
+    let mut clauses = vec![];
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        if break_chars.contains(&c) {
+            let end = i + c.len_utf8();
+            clauses.push(&text[start..end]);
+            start = end;
+        }
+    }
+    if start < text.len() {
+        clauses.push(&text[start..]);
+    }
+    clauses
+}
+
+/// Byte offset of the subslice `sub` within `text`.
+fn offset_in(text: &str, sub: &str) -> usize {
+    sub.as_ptr() as usize - text.as_ptr() as usize
+}
+
+/// Hard char-count slicing fallback for a single clause that alone exceeds
+/// `split_chunk_size`.
+fn hard_split(text: &str, split_chunk_size: usize) -> Vec<&str> {
+    let char_indices: Vec<usize> = text.char_indices().map(|(i, _)| i).collect();
+    let mut chunks = vec![];
+    let mut start = 0;
+    let mut count = 0;
+    for (n, _) in char_indices.iter().enumerate() {
+        count += 1;
+        if count == split_chunk_size {
+            let end = char_indices.get(n + 1).copied().unwrap_or(text.len());
+            chunks.push(&text[start..end]);
+            start = end;
+            count = 0;
+        }
+    }
+    if start < text.len() {
+        chunks.push(&text[start..]);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accumulates_clauses_up_to_chunk_size() {
+        let options = SplitOptions::new(10);
+        assert_eq!(
+            split_text_with_options("ab,cd,ef,gh,ij", &options),
+            vec!["ab,cd,ef,", "gh,ij"]
+        );
+    }
+
+    #[test]
+    fn hard_splits_a_single_clause_longer_than_the_limit() {
+        let options = SplitOptions::new(4);
+        assert_eq!(
+            split_text_with_options("abcdefgh", &options),
+            vec!["abcd", "efgh"]
+        );
+    }
+
+    #[test]
+    fn flushes_the_pending_chunk_before_a_hard_split() {
+        let options = SplitOptions::new(5);
+        assert_eq!(
+            split_text_with_options("ab,abcdefgh", &options),
+            vec!["ab,", "abcde", "fgh"]
+        );
+    }
+
+    #[test]
+    fn empty_text_yields_no_chunks() {
+        assert_eq!(split_text_with_options("", &SplitOptions::new(10)), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn hard_split_slices_by_char_count_not_byte_count() {
+        assert_eq!(hard_split("你好世界", 2), vec!["你好", "世界"]);
+    }
+}
@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use tch::{IValue, Kind, Tensor};
+use tokenizers::Tokenizer;
+
+use crate::GPTSovits;
+
+use super::{classify_char, symbol_id, zero_bert, Lang, BERT_FEATURE_DIM};
+
+const INITIALS: &[&str] = &[
+    "zh", "ch", "sh", "b", "p", "m", "f", "d", "t", "n", "l", "g", "k", "h", "j", "q", "x", "r",
+    "z", "c", "s", "y", "w",
+];
+
+/// Splits a toned pinyin syllable (e.g. `"ni3"`) into its initial and final,
+/// matching the symbol table's separate initial/final phoneme entries.
+fn split_initial_final(pinyin: &str) -> (Option<&str>, &str) {
+    for initial in INITIALS {
+        if pinyin.starts_with(initial) && pinyin.len() > initial.len() {
+            return (Some(*initial), &pinyin[initial.len()..]);
+        }
+    }
+    (None, pinyin)
+}
+
+/// Chinese BERT model used to derive per-phone prosody features. Optional:
+/// when no model was configured (`GPTSovitsConfig::with_chinese` wasn't
+/// called), it falls back to zero features so the crate still loads for
+/// english/japanese-only setups.
+#[derive(Debug, Default)]
+pub struct CNBertModel {
+    bert: Option<Arc<tch::CModule>>,
+    tokenizer: Option<Arc<Tokenizer>>,
+}
+
+impl CNBertModel {
+    pub fn new(bert: Arc<tch::CModule>, tokenizer: Arc<Tokenizer>) -> Self {
+        Self {
+            bert: Some(bert),
+            tokenizer: Some(tokenizer),
+        }
+    }
+
+    /// Computes one bert feature vector per phone in `phone_counts_per_char`
+    /// (the number of phones each source char expanded into), broadcasting
+    /// each char's hidden state across its phones.
+    fn bert_features(
+        &self,
+        gsv: &GPTSovits,
+        text: &str,
+        phone_counts_per_char: &[i64],
+    ) -> anyhow::Result<Tensor> {
+        let total_phones: i64 = phone_counts_per_char.iter().sum();
+        let (bert, tokenizer) = match (&self.bert, &self.tokenizer) {
+            (Some(bert), Some(tokenizer)) => (bert, tokenizer),
+            _ => return Ok(zero_bert(gsv, total_phones)),
+        };
+
+        let encoding = tokenizer
+            .encode(text, true)
+            .map_err(|e| anyhow::anyhow!("tokenize error: {}", e))?;
+        let ids: Vec<i64> = encoding.get_ids().iter().map(|&id| id as i64).collect();
+        let input = Tensor::from_slice(&ids)
+            .unsqueeze(0)
+            .to_device(gsv.device);
+
+        let output = bert.method_is("forward", &[&IValue::Tensor(input)])?;
+        let hidden = match output {
+            IValue::Tensor(hidden) => hidden,
+            IValue::TensorList(mut hiddens) => hiddens.remove(0),
+            _ => anyhow::bail!("unexpected bert model output"),
+        }
+        // [1, seq_len, hidden] -> [hidden, seq_len]
+        .squeeze_dim(0)
+        .transpose(0, 1)
+        .to_kind(Kind::Float);
+
+        let seq_len = hidden.size()[1];
+        let mut columns = Vec::with_capacity(total_phones as usize);
+        for (char_index, &count) in phone_counts_per_char.iter().enumerate() {
+            // +1 skips the leading [CLS] token the tokenizer prepends.
+            let token_index = (char_index as i64 + 1).min(seq_len - 1).max(0);
+            let column = hidden.narrow(1, token_index, 1);
+            for _ in 0..count {
+                columns.push(column.shallow_clone());
+            }
+        }
+        if columns.is_empty() {
+            Ok(zero_bert(gsv, 0))
+        } else {
+            Ok(Tensor::cat(&columns, 1).to_kind(gsv.kind))
+        }
+    }
+}
+
+pub(crate) fn get_phone_and_bert(gsv: &GPTSovits, text: &str) -> anyhow::Result<(Tensor, Tensor)> {
+    let words = gsv.jieba.cut(text, true);
+
+    let mut phone_ids = vec![];
+    let mut phone_counts_per_char = vec![];
+    for word in &words {
+        for (char_index, c) in word.chars().enumerate() {
+            // Non-Hanzi chars (punctuation, digits, whitespace) end up in a
+            // zh run via lang_seg's neighbor-attaching, but g2pw only knows
+            // how to disambiguate actual Hanzi readings; route these straight
+            // to their own symbol instead of feeding them through the model.
+            if classify_char(c) != Some(Lang::Zh) {
+                let symbol = if c.is_whitespace() { "SP".to_string() } else { c.to_string() };
+                phone_ids.push(symbol_id(gsv, &symbol));
+                phone_counts_per_char.push(1);
+                continue;
+            }
+
+            let pinyin = gsv.g2pw.convert(word, char_index)?;
+            let (initial, final_) = split_initial_final(&pinyin);
+            let mut count = 0;
+            if let Some(initial) = initial {
+                phone_ids.push(symbol_id(gsv, initial));
+                count += 1;
+            }
+            phone_ids.push(symbol_id(gsv, final_));
+            count += 1;
+            phone_counts_per_char.push(count);
+        }
+    }
+
+    let phone_seq = Tensor::from_slice(&phone_ids).to_device(gsv.device);
+    let bert_seq = gsv.zh_bert.bert_features(gsv, text, &phone_counts_per_char)?;
+    debug_assert_eq!(bert_seq.size()[0], BERT_FEATURE_DIM);
+    Ok((phone_seq, bert_seq))
+}